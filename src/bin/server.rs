@@ -0,0 +1,42 @@
+//! The SSR binary: serves the Leptos app built in `src/ui.rs`, spawns the
+//! background actor thread (`duo_driver::spawn_actor_thread`) that polls
+//! Duolingo and fills in `SHARED_STATE`/`STATUS_TX`/`HISTORY_POOL`, and
+//! mounts `/api/sse` alongside the generated server-fn routes. This is the
+//! only thing that actually runs the `ssr`-feature code in `lib.rs`; add
+//! `[[bin]] name = "server"` `required-features = ["ssr"]` to Cargo.toml.
+
+use axum::routing::get;
+use axum::Router;
+use duo_enforcer::ui::{shell, status_sse_handler, App};
+use leptos::prelude::*;
+use leptos_axum::{generate_route_list, LeptosRoutes};
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    duo_enforcer::init_tracing().expect("Failed to init tracing");
+
+    let conf = leptos::config::get_configuration(None).expect("Failed to read leptos config");
+    let leptos_options = conf.leptos_options;
+    let addr = leptos_options.site_addr;
+    let routes = generate_route_list(App);
+
+    duo_enforcer::spawn_actor_thread(tokio::runtime::Handle::current());
+
+    let app = Router::new()
+        .route("/api/sse", get(status_sse_handler))
+        .leptos_routes(&leptos_options, routes, {
+            let leptos_options = leptos_options.clone();
+            move || shell(leptos_options.clone())
+        })
+        .fallback(leptos_axum::file_and_error_handler(shell))
+        .with_state(leptos_options);
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .unwrap_or_else(|e| panic!("Failed to bind to {addr}: {e}"));
+    tracing::info!("Listening on http://{addr}");
+    axum::serve(listener, app.into_make_service())
+        .await
+        .expect("Server error");
+}
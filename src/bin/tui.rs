@@ -0,0 +1,236 @@
+//! Headless ratatui client for SSH/terminal setups where a browser isn't an
+//! option. Connects to a running server over HTTP, renders the same
+//! blocked/unblocked XP banner as the Leptos UI plus a scrollable list of
+//! lessons (raw unix timestamps, not grouped by day), and lets `s` force a
+//! poll.
+//!
+//! Requires the `tui` feature (pulls in ratatui/crossterm); add
+//! `[[bin]] name = "tui"` `required-features = ["tui"]` to Cargo.toml.
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{bounded, Receiver};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use duo_enforcer::SharedState;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Terminal;
+use std::io;
+use std::time::Duration;
+
+/// Tracks how much of the (possibly multi-line-wrapped) lesson log is
+/// scrolled off the top of the pane, recomputed against the pane's current
+/// size on every render since a terminal resize changes how many rows each
+/// line wraps to.
+struct History {
+    lines: Vec<String>,
+    offset: u16,
+    count: u16,
+    height: u16,
+    width: u16,
+}
+
+impl History {
+    fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            offset: 0,
+            count: 0,
+            height: 0,
+            width: 0,
+        }
+    }
+
+    /// Recomputes `count` as the total wrapped row count for `height`/`width`,
+    /// then clamps `offset` so the view never scrolls past the bottom.
+    fn recompute(&mut self, height: u16, width: u16) {
+        self.height = height;
+        self.width = width.max(1);
+        self.count = self
+            .lines
+            .iter()
+            .map(|line| (line.len() as u16 / self.width) + 1)
+            .sum();
+        self.offset = self.offset.min(self.count.saturating_sub(self.height));
+    }
+
+    fn up(&mut self, n: u16) {
+        self.offset = self.offset.saturating_sub(n);
+    }
+
+    fn down(&mut self, n: u16) {
+        if self.count < self.height {
+            return;
+        }
+        let max_advance = (self.count - self.height).saturating_sub(self.offset);
+        self.offset += n.min(max_advance);
+    }
+}
+
+/// Logs in (if `DUO_ADMIN_PASSWORD` is set) and returns the session cookie to
+/// attach to `force_poll_now` calls, matching the Argon2 gate on the server.
+fn login(client: &reqwest::blocking::Client, base_url: &str) -> Result<Option<String>> {
+    let Ok(password) = std::env::var("DUO_ADMIN_PASSWORD") else {
+        return Ok(None);
+    };
+
+    let resp = client
+        .post(format!("{base_url}/api/Login"))
+        .json(&serde_json::json!({ "password": password }))
+        .send()
+        .context("Failed to reach /api/Login")?;
+
+    Ok(resp
+        .headers()
+        .get(reqwest::header::SET_COOKIE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string()))
+}
+
+fn force_poll(client: &reqwest::blocking::Client, base_url: &str, cookie: Option<&str>) {
+    let mut req = client.post(format!("{base_url}/api/ForcePollNow"));
+    if let Some(cookie) = cookie {
+        req = req.header(reqwest::header::COOKIE, cookie);
+    }
+    let _ = req.send();
+}
+
+/// Spawns a background thread that streams `/api/sse` and forwards each
+/// decoded `SharedState` frame to the returned channel.
+fn spawn_sse_thread(base_url: String) -> Receiver<SharedState> {
+    let (tx, rx) = bounded(16);
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+        rt.block_on(async move {
+            loop {
+                let resp = match reqwest::get(format!("{base_url}/api/sse")).await {
+                    Ok(resp) => resp,
+                    Err(_) => {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        continue;
+                    }
+                };
+
+                use futures_util::StreamExt;
+                let mut stream = resp.bytes_stream();
+                let mut buf = String::new();
+                while let Some(chunk) = stream.next().await {
+                    let Ok(chunk) = chunk else { break };
+                    buf.push_str(&String::from_utf8_lossy(&chunk));
+                    while let Some(pos) = buf.find('\n') {
+                        let line = buf[..pos].trim_end_matches('\r').to_string();
+                        buf.drain(..=pos);
+                        if let Some(data) = line.strip_prefix("data: ").or_else(|| line.strip_prefix("data:")) {
+                            if let Ok(state) = serde_json::from_str::<SharedState>(data.trim()) {
+                                let _ = tx.send(state);
+                            }
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        });
+    });
+    rx
+}
+
+fn lesson_lines(status: &SharedState) -> Vec<String> {
+    status
+        .lessons
+        .iter()
+        .map(|l| format!("{}: XP={}", l.time, l.xp))
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let base_url =
+        std::env::var("DUO_SERVER_URL").unwrap_or_else(|_| "http://127.0.0.1:3000".to_string());
+
+    let client = reqwest::blocking::Client::new();
+    let cookie = login(&client, &base_url).ok().flatten();
+
+    let status_rx = spawn_sse_thread(base_url.clone());
+    let mut status = SharedState::default();
+    let mut history = History::new();
+
+    enable_raw_mode().context("Failed to enable raw mode")?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen).context("Failed to enter alternate screen")?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend).context("Failed to start terminal")?;
+
+    let result = run(&mut terminal, &client, &base_url, cookie.as_deref(), &status_rx, &mut status, &mut history);
+
+    disable_raw_mode().ok();
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).ok();
+
+    result
+}
+
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    client: &reqwest::blocking::Client,
+    base_url: &str,
+    cookie: Option<&str>,
+    status_rx: &Receiver<SharedState>,
+    status: &mut SharedState,
+    history: &mut History,
+) -> Result<()> {
+    loop {
+        while let Ok(new_status) = status_rx.try_recv() {
+            history.lines = lesson_lines(&new_status);
+            *status = new_status;
+        }
+
+        terminal.draw(|frame| {
+            let area = frame.area();
+            let layout = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).split(area);
+
+            let color = if status.blocked { Color::Red } else { Color::Green };
+            let banner = if status.blocked {
+                format!("BLOCKED! XP: {}/{}", status.xp_today, status.xp_goal)
+            } else {
+                format!("UNBLOCKED! XP: {}/{}", status.xp_today, status.xp_goal)
+            };
+            frame.render_widget(
+                Paragraph::new(banner)
+                    .style(Style::default().fg(color))
+                    .block(Block::default().borders(Borders::ALL).title("Status")),
+                layout[0],
+            );
+
+            let pane = Block::default().borders(Borders::ALL).title("Recent Lessons");
+            let inner = pane.inner(layout[1]);
+            history.recompute(inner.height, inner.width);
+            let lines: Vec<Line> = history.lines.iter().map(|l| Line::from(l.as_str())).collect();
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .wrap(Wrap { trim: false })
+                    .scroll((history.offset, 0))
+                    .block(pane),
+                layout[1],
+            );
+        })?;
+
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('s') => force_poll(client, base_url, cookie),
+                    KeyCode::Up => history.up(1),
+                    KeyCode::Down => history.down(1),
+                    KeyCode::PageUp => history.up(history.height),
+                    KeyCode::PageDown => history.down(history.height),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! TOML-loaded enforcement schedule: how often to poll, the XP goal for each
+//! weekday, and an optional grace time that moves the reported reset time
+//! past midnight. Reloadable at runtime via `ActorCommand::ReloadConfig` so
+//! schedule edits (a rest day, a weekend goal) don't require a restart.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Weekday};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn default_poll_interval_secs() -> u64 {
+    300
+}
+
+fn default_xp_goal() -> HashMap<String, i64> {
+    ["mon", "tue", "wed", "thu", "fri", "sat", "sun"]
+        .into_iter()
+        .map(|day| (day.to_string(), 50))
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Three-letter lowercase weekday (`mon`..`sun`) to that day's XP goal.
+    /// Days missing from the map fall back to 50 (see `goal_for`).
+    #[serde(default = "default_xp_goal")]
+    pub xp_goal: HashMap<String, i64>,
+    /// "HH:MM" local time reported as the day's reset instead of literal
+    /// midnight, e.g. `"02:00"` for a late-night grace period. It only moves
+    /// `next_reset_at`; XP is still counted from local midnight and the
+    /// day's goal is still picked by calendar weekday.
+    #[serde(default)]
+    pub grace_until: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: default_poll_interval_secs(),
+            xp_goal: default_xp_goal(),
+            grace_until: None,
+        }
+    }
+}
+
+const WEEKDAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+impl Config {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+
+    /// The configured XP requirement for `weekday`, defaulting to 50 if the
+    /// config doesn't mention that day.
+    pub fn goal_for(&self, weekday: Weekday) -> i64 {
+        let key = WEEKDAY_KEYS[weekday.num_days_from_monday() as usize];
+        self.xp_goal.get(key).copied().unwrap_or(50)
+    }
+
+    /// Parses `grace_until`. `load_config` rejects a malformed value up
+    /// front, so by the time a `Config` exists this can only be `None`
+    /// (literal midnight) or a value already known to parse.
+    fn cutoff_time(&self) -> NaiveTime {
+        self.grace_until
+            .as_deref()
+            .map(|s| {
+                NaiveTime::parse_from_str(s, "%H:%M")
+                    .unwrap_or_else(|_| panic!("grace_until should have been validated at load time: {s}"))
+            })
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(0, 0, 0).unwrap())
+    }
+
+    /// The next unix timestamp at which the enforcement day rolls over --
+    /// either the next literal midnight, or the next `grace_until` if one is
+    /// configured.
+    pub fn next_reset_at(&self, now: DateTime<Local>) -> i64 {
+        let cutoff = self.cutoff_time();
+        let today_cutoff = now.date_naive().and_time(cutoff);
+        let candidate = if now.naive_local() < today_cutoff {
+            today_cutoff
+        } else {
+            today_cutoff + ChronoDuration::days(1)
+        };
+
+        Local
+            .from_local_datetime(&candidate)
+            .single()
+            .map(|dt| dt.timestamp())
+            .unwrap_or_else(|| now.timestamp())
+    }
+}
+
+/// Loads config from `DUO_CONFIG_PATH` (default `duo_config.toml`). A
+/// missing file is treated as "use the defaults" rather than an error, so a
+/// fresh checkout still runs; a malformed file -- including a `grace_until`
+/// that doesn't parse as `"HH:MM"` -- is an error so a typo'd reload
+/// doesn't silently fall back.
+pub fn load_config() -> Result<Config> {
+    let path = std::env::var("DUO_CONFIG_PATH").unwrap_or_else(|_| "duo_config.toml".to_string());
+    let config: Config = match std::fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents).with_context(|| format!("Failed to parse {path}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {path}")),
+    };
+
+    if let Some(grace_until) = &config.grace_until {
+        NaiveTime::parse_from_str(grace_until, "%H:%M").with_context(|| {
+            format!("grace_until {grace_until:?} in {path} must be in \"HH:MM\" format")
+        })?;
+    }
+
+    Ok(config)
+}
@@ -1,8 +1,6 @@
-use chrono::{Datelike, Local, NaiveDateTime, Utc};
+use chrono::{Datelike, Local};
 use crossbeam_channel::Receiver;
 use crossbeam_channel::RecvTimeoutError;
-use leptos::*;
-use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 use std::sync::OnceLock;
 use std::time::Instant;
@@ -13,26 +11,109 @@ use std::{
     thread,
     time::Duration,
 };
+use tokio::runtime::Handle;
+use tokio::sync::watch;
+use tracing::{info, warn};
 
-use crate::{duolingo::Duolingo, ActorCommand, SharedState};
+use crate::config::{self, Config};
+use crate::notify::Notifier;
+use crate::{duolingo::DuolingoApi, history, ActorCommand, Lesson, SharedState};
 
-#[cfg(feature = "ssr")]
 pub(crate) static SHARED_STATE: LazyLock<Arc<Mutex<SharedState>>> =
     LazyLock::new(|| Arc::new(Mutex::new(SharedState::default())));
-#[cfg(feature = "ssr")]
 pub(crate) static CMD_TX: OnceLock<crossbeam_channel::Sender<ActorCommand>> = OnceLock::new();
+/// Pushed to on every `poll_duo` mutation so `/api/sse` can stream updates
+/// instead of clients refetching `get_status` on a timer.
+pub(crate) static STATUS_TX: OnceLock<watch::Sender<SharedState>> = OnceLock::new();
+/// The history database, opened once on the actor thread. `get_history`
+/// reads from this same pool.
+pub(crate) static HISTORY_POOL: OnceLock<sqlx::SqlitePool> = OnceLock::new();
+/// ntfy/webhook/desktop/SMTP alerts on a `blocked` edge transition;
+/// `Notifier` itself skips calls where the state didn't change.
+static NOTIFIER: LazyLock<Notifier> = LazyLock::new(Notifier::from_env);
 
-// The actor thread: polls Duolingo every 5 minutes or on command
-fn spawn_actor_thread(rx: Receiver<ActorCommand>) {
+/// Sets up `tracing`, adding an OTLP export layer when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` is set so poll spans can be shipped to a
+/// collector instead of only grepped out of local logs. Falls back to a
+/// plain `fmt` subscriber when the env var is unset.
+pub fn init_tracing() -> anyhow::Result<()> {
+    use anyhow::Context;
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"));
+    let fmt_layer = tracing_subscriber::fmt::layer();
+    let registry = tracing_subscriber::registry().with(env_filter).with(fmt_layer);
+
+    match std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(endpoint) => {
+            use opentelemetry::trace::TracerProvider as _;
+
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(&endpoint)
+                .build()
+                .context("Failed to build OTLP exporter")?;
+
+            let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+                .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+                .build();
+            let tracer = provider.tracer("duo-enforcer-ssr");
+            opentelemetry::global::set_tracer_provider(provider);
+
+            registry
+                .with(tracing_opentelemetry::layer().with_tracer(tracer))
+                .try_init()
+                .context("Failed to init tracing subscriber")
+        }
+        Err(_) => registry.try_init().context("Failed to init tracing subscriber"),
+    }
+}
+
+/// Spawns the actor thread and wires up `CMD_TX`/`STATUS_TX`/`HISTORY_POOL`
+/// so the server fns in `ui.rs` (`get_status`, `force_poll_now`, ...) and
+/// `status_sse_handler` have somewhere to reach. Call once, from the SSR
+/// binary's `main`, before the axum server starts serving.
+pub fn spawn_actor_thread(rt_handle: Handle) {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let _ = CMD_TX.set(tx);
+    thread::spawn(move || actor_loop(rx, rt_handle));
+}
+
+// The actor thread body: polls Duolingo every `poll_interval_secs` or on
+// command. `rt_handle` lets this plain OS thread drive the async sqlx/
+// DuolingoApi calls via `block_on`.
+fn actor_loop(rx: Receiver<ActorCommand>, rt_handle: Handle) {
     // read initial JWT from env
     let initial_jwt = std::env::var("DUO_JWT").unwrap_or_else(|_| "".to_string());
-    let xp_req = 50; // daily XP requirement
     let done_file = dirs::home_dir()
         .unwrap_or_else(|| "/tmp".into())
         .join(".cache/duo-done");
 
-    // Try to init Duolingo
-    let mut duo = match Duolingo::new(&initial_jwt) {
+    let mut config = match config::load_config() {
+        Ok(c) => c,
+        Err(e) => {
+            let mut st = SHARED_STATE.lock().unwrap();
+            st.last_error = Some(format!("Failed to load config, using defaults: {e}"));
+            drop(st);
+            Config::default()
+        }
+    };
+
+    let (status_tx, _) = watch::channel(SharedState::default());
+    let _ = STATUS_TX.set(status_tx.clone());
+
+    match rt_handle.block_on(history::connect()) {
+        Ok(pool) => {
+            let _ = HISTORY_POOL.set(pool);
+        }
+        Err(e) => {
+            let mut st = SHARED_STATE.lock().unwrap();
+            st.last_error = Some(format!("Failed to open history db: {e}"));
+        }
+    }
+
+    // Try to init Duolingo, authenticating against whatever JWT we have.
+    let mut duo = match DuolingoApi::new() {
         Ok(d) => Some(d),
         Err(e) => {
             let mut st = SHARED_STATE.lock().unwrap();
@@ -40,67 +121,191 @@ fn spawn_actor_thread(rx: Receiver<ActorCommand>) {
             None
         }
     };
+    if let (Some(d), false) = (duo.as_mut(), initial_jwt.is_empty()) {
+        if let Err(e) = rt_handle.block_on(d.update_jwt(&initial_jwt)) {
+            let mut st = SHARED_STATE.lock().unwrap();
+            st.last_error = Some(format!("init error: {e}"));
+        }
+    }
 
     let mut next_poll = Instant::now();
     loop {
         match rx.recv_deadline(next_poll) {
             Ok(cmd) => match cmd {
-                ActorCommand::UpdateJWT(new_jwt) => match Duolingo::new(&new_jwt) {
-                    Ok(d) => {
-                        duo = Some(d);
-                        let mut st = SHARED_STATE.lock().unwrap();
-                        st.last_error = Some("JWT updated OK".to_string());
+                ActorCommand::UpdateJWT(new_jwt) => {
+                    let _span = tracing::info_span!("handle_command", cmd = "UpdateJWT").entered();
+                    match DuolingoApi::new() {
+                        Ok(mut d) => match rt_handle.block_on(d.update_jwt(&new_jwt)) {
+                            Ok(()) => {
+                                duo = Some(d);
+                                SHARED_STATE.lock().unwrap().last_error = Some("JWT updated OK".to_string());
+                            }
+                            Err(e) => {
+                                warn!("JWT update failed: {e}");
+                                SHARED_STATE.lock().unwrap().last_error = Some(format!("JWT update failed: {e}"));
+                            }
+                        },
+                        Err(e) => {
+                            warn!("JWT update failed: {e}");
+                            SHARED_STATE.lock().unwrap().last_error = Some(format!("JWT update failed: {e}"));
+                        }
                     }
-                    Err(e) => {
-                        let mut st = SHARED_STATE.lock().unwrap();
-                        st.last_error = Some(format!("JWT update failed: {e}"));
+                }
+                ActorCommand::ForcePoll => {
+                    let _span = tracing::info_span!("handle_command", cmd = "ForcePoll").entered();
+                    poll_duo(&mut duo, &config, &done_file, &status_tx, &rt_handle)
+                }
+                ActorCommand::ReloadConfig => {
+                    let _span = tracing::info_span!("handle_command", cmd = "ReloadConfig").entered();
+                    match config::load_config() {
+                        Ok(c) => {
+                            config = c;
+                            let mut st = SHARED_STATE.lock().unwrap();
+                            st.last_error = Some("Config reloaded OK".to_string());
+                        }
+                        Err(e) => {
+                            warn!("Config reload failed: {e}");
+                            let mut st = SHARED_STATE.lock().unwrap();
+                            st.last_error = Some(format!("Config reload failed: {e}"));
+                        }
                     }
-                },
-                ActorCommand::ForcePoll => poll_duo(&mut duo, xp_req, &done_file),
+                }
                 ActorCommand::Shutdown => {
-                    // optional graceful exit
+                    let _span = tracing::info_span!("handle_command", cmd = "Shutdown").entered();
+                    info!("Actor thread shutting down");
                     return;
                 }
             },
             Err(RecvTimeoutError::Timeout) => {
-                poll_duo(&mut duo, xp_req, &done_file);
+                poll_duo(&mut duo, &config, &done_file, &status_tx, &rt_handle);
+                next_poll += config.poll_interval();
             }
             Err(RecvTimeoutError::Disconnected) => {
                 // main sender is gone => just exit
                 return;
             }
         }
-        next_poll += Duration::from_secs(30);
     }
 }
 
-fn poll_duo(duo: &mut Option<Duolingo>, xp_req: i64, done_file: &PathBuf) {
-    if let Some(d) = duo {
-        match d.get_daily_xp_progress() {
+#[tracing::instrument(skip_all, fields(xp_today, xp_goal, blocked))]
+fn poll_duo(
+    duo: &mut Option<DuolingoApi>,
+    config: &Config,
+    done_file: &PathBuf,
+    status_tx: &watch::Sender<SharedState>,
+    rt_handle: &Handle,
+) {
+    let now = Local::now();
+    let xp_req = config.goal_for(now.weekday());
+    let next_reset_at = config.next_reset_at(now);
+    // Read off the JWT's own expiry regardless of which branch below runs,
+    // so the UI can warn about an about-to-expire token even on a poll
+    // error or while there's no client at all.
+    let token_expires_at = duo.as_ref().and_then(DuolingoApi::expires_at);
+    let token_expired = duo.as_ref().is_some_and(DuolingoApi::is_expired);
+
+    let snapshot = if let Some(d) = duo {
+        let progress = tracing::info_span!("duolingo_get_daily_progress")
+            .in_scope(|| rt_handle.block_on(d.get_daily_progress()));
+        match progress {
             Ok(prog) => {
+                let xp_today = i64::from(prog.xp_today);
+                let lessons_today: Vec<Lesson> = prog
+                    .lessons_today
+                    .into_iter()
+                    .map(|l| Lesson { time: l.time, xp: i64::from(l.xp) })
+                    .collect();
+
                 let mut st = SHARED_STATE.lock().unwrap();
-                st.xp_goal = prog.xp_goal;
-                st.xp_today = prog.xp_today;
-                st.lessons = prog.lessons_today;
+                let was_blocked = st.blocked;
+                // The day's *effective* goal is the configured schedule, not
+                // whatever goal Duolingo itself has on file for the user.
+                st.xp_goal = xp_req;
+                st.xp_today = xp_today;
+                st.lessons = lessons_today;
                 st.last_error = None;
+                st.next_reset_at = next_reset_at;
+                st.token_expires_at = token_expires_at;
+                st.expired = token_expired;
                 // blocked?
-                st.blocked = prog.xp_today < xp_req;
-                if prog.xp_today >= xp_req {
+                st.blocked = xp_today < xp_req;
+                let span = tracing::Span::current();
+                span.record("xp_today", st.xp_today);
+                span.record("xp_goal", st.xp_goal);
+                span.record("blocked", st.blocked);
+                let goal_met = xp_today >= xp_req;
+                if goal_met {
                     // write done file
                     let today_str = Local::now().format("%Y-%m-%d").to_string();
                     let _ = fs::create_dir_all(done_file.parent().unwrap());
                     if let Err(e) = fs::write(done_file, &today_str) {
                         st.last_error = Some(format!("Failed to write done file: {e}"));
                     }
+                } else if let Err(e) = fs::remove_file(done_file) {
+                    // Re-arm: a stale done file from a previous day (or from
+                    // before xp dropped back below goal) must not keep the
+                    // block lifted. Missing is the common case, not an error.
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        st.last_error = Some(format!("Failed to remove done file: {e}"));
+                    }
+                }
+                let snap = st.clone();
+                drop(st);
+
+                if let Some(pool) = HISTORY_POOL.get() {
+                    let today_str = Local::now().format("%Y-%m-%d").to_string();
+                    let result = rt_handle.block_on(async {
+                        history::upsert_lessons(pool, &snap.lessons).await?;
+                        history::upsert_daily_rollup(pool, &today_str, snap.xp_today, goal_met).await
+                    });
+                    if let Err(e) = result {
+                        SHARED_STATE.lock().unwrap().last_error = Some(format!("Failed to persist history: {e}"));
+                    }
                 }
+
+                let detail = format!(
+                    "XP: {}/{}\nTime til reset: {}\nLast error: {}",
+                    snap.xp_today,
+                    snap.xp_goal,
+                    time_until_reset(snap.next_reset_at),
+                    snap.last_error.as_deref().unwrap_or("none"),
+                );
+                if let Some(err) =
+                    rt_handle.block_on(NOTIFIER.notify_transition(was_blocked, snap.blocked, &detail))
+                {
+                    SHARED_STATE.lock().unwrap().last_error = Some(err);
+                }
+
+                snap
             }
             Err(e) => {
+                warn!("Poll error: {e}");
                 let mut st = SHARED_STATE.lock().unwrap();
                 st.last_error = Some(format!("Poll error: {e}"));
+                st.next_reset_at = next_reset_at;
+                st.token_expires_at = token_expires_at;
+                st.expired = token_expired;
+                st.clone()
             }
         }
     } else {
         let mut st = SHARED_STATE.lock().unwrap();
         st.last_error = Some("No duolingo client available (JWT init failed)".to_string());
-    }
+        st.next_reset_at = next_reset_at;
+        st.token_expires_at = token_expires_at;
+        st.expired = token_expired;
+        st.clone()
+    };
+
+    // No subscribers is not an error; nobody has `/api/sse` open yet.
+    let _ = status_tx.send(snapshot);
+}
+
+/// Same "hours and minutes until the configured reset" format shown in
+/// `OkUI`, against `SharedState::next_reset_at` rather than literal
+/// midnight so a configured `grace_until` is reflected here too.
+fn time_until_reset(next_reset_at: i64) -> String {
+    let diff = next_reset_at - chrono::Utc::now().timestamp();
+    format!("{}h {}m", diff / 3600, (diff / 60) % 60)
 }
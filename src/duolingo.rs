@@ -1,8 +1,55 @@
 use anyhow::{anyhow, bail, Context, Result};
-use chrono::{Local, NaiveTime};
-use log::{debug, error};
-use reqwest::Client;
+use chrono::{Local, NaiveTime, Utc};
+use hickory_resolver::config::ResolverConfig;
+use hickory_resolver::TokioAsyncResolver;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{debug, error};
+
+/// Shown instead of a generic auth failure once we know the JWT is past its
+/// `exp`, so the user knows exactly what to do instead of grepping logs.
+const EXPIRED_JWT_MSG: &str = "JWT expired, paste a fresh token";
+
+/// `reqwest::dns::Resolve` backed by hickory-dns, so lookups keep working
+/// even on networks where the local resolver is broken or hijacked -
+/// exactly the kind of locked-down network an enforcement tool runs on.
+struct HickoryResolver(Arc<TokioAsyncResolver>);
+
+impl Resolve for HickoryResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            let addrs: Addrs = Box::new(lookup.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+/// Picks a DNS resolver based on `DUO_DNS_MODE` (`system` (default),
+/// `hickory` for a plain hickory-dns resolver, or `doh` for DNS-over-HTTPS).
+/// `DUO_DOH_UPSTREAM` selects the DoH upstream (`cloudflare` (default) or
+/// `google`) when `DUO_DNS_MODE=doh`.
+fn resolver_from_env() -> Option<Arc<dyn Resolve>> {
+    let mode = std::env::var("DUO_DNS_MODE").unwrap_or_else(|_| "system".to_string());
+    let config = match mode.as_str() {
+        "doh" => {
+            let upstream = std::env::var("DUO_DOH_UPSTREAM").unwrap_or_else(|_| "cloudflare".to_string());
+            match upstream.as_str() {
+                "google" => ResolverConfig::google_https(),
+                _ => ResolverConfig::cloudflare_https(),
+            }
+        }
+        "hickory" => ResolverConfig::default(),
+        _ => return None,
+    };
+
+    let resolver = TokioAsyncResolver::tokio(config, Default::default());
+    Some(Arc::new(HickoryResolver(Arc::new(resolver))))
+}
 
 /// Minimal wrapper of the duolingo api.
 /// Requires copying a token from a properly logged-in browser instance.
@@ -11,11 +58,15 @@ pub struct DuolingoApi {
     client: Client,
     jwt: Option<String>,
     user_id: Option<String>,
+    jwt_expires_at: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct JWTClaims {
     sub: serde_json::Value,
+    exp: i64,
+    #[allow(dead_code)]
+    iat: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,19 +86,35 @@ pub struct Lesson {
 
 impl DuolingoApi {
     pub fn new() -> Result<Self> {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) Chrome/83.0.4103.116 DuolingoEnforcer/1.0")
-            .build()
-            .context("Failed to build request client")?;
+        let mut builder = Client::builder()
+            .user_agent("Mozilla/5.0 (X11; Linux x86_64) Chrome/83.0.4103.116 DuolingoEnforcer/1.0");
+        if let Some(resolver) = resolver_from_env() {
+            builder = builder.dns_resolver(resolver);
+        }
+        let client = builder.build().context("Failed to build request client")?;
 
         Ok(Self {
             client,
             jwt: None,
             user_id: None,
+            jwt_expires_at: None,
         })
     }
 
+    /// Unix-second timestamp the current JWT's `exp` claim falls on, if we
+    /// have a JWT at all.
+    pub fn expires_at(&self) -> Option<i64> {
+        self.jwt_expires_at
+    }
+
+    /// Whether the current JWT's `exp` claim is already in the past.
+    pub fn is_expired(&self) -> bool {
+        self.jwt_expires_at
+            .is_some_and(|exp| Utc::now().timestamp() >= exp)
+    }
+
     /// If we used an empty string for JWT, or want to change it later
+    #[tracing::instrument(skip(self, new_jwt))]
     pub async fn update_jwt(&mut self, new_jwt: &str) -> Result<()> {
         self.jwt = Some(new_jwt.to_string());
 
@@ -64,13 +131,23 @@ impl DuolingoApi {
         )
         .map_err(|e| anyhow::anyhow!("Failed to decode JWT's sub: {e}"))?;
         let user_id = token_data.claims.sub.to_string();
+        self.jwt_expires_at = Some(token_data.claims.exp);
+
+        if self.is_expired() {
+            bail!(EXPIRED_JWT_MSG);
+        }
 
         self.check_auth(&user_id).await?;
         self.user_id = Some(user_id);
         Ok(())
     }
 
+    #[tracing::instrument(skip(self), fields(status))]
     async fn check_auth(&mut self, user_id: &str) -> Result<()> {
+        if self.is_expired() {
+            bail!(EXPIRED_JWT_MSG);
+        }
+
         let jwt = self.jwt.as_ref().ok_or_else(|| anyhow!("Missing jwt"))?;
         let url = format!(
             "https://www.duolingo.com/2017-06-30/users/{}?fields=username",
@@ -83,6 +160,10 @@ impl DuolingoApi {
             .bearer_auth(jwt)
             .send()
             .await?;
+        tracing::Span::current().record("status", resp.status().as_u16());
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            bail!(EXPIRED_JWT_MSG);
+        }
         if resp.status() != 200 {
             bail!("Failed to fetch username (status={})", resp.status());
         }
@@ -97,7 +178,12 @@ impl DuolingoApi {
         Ok(())
     }
 
+    #[tracing::instrument(skip(self), fields(xp_today))]
     pub async fn get_daily_progress(&self) -> Result<DailyProgress> {
+        if self.is_expired() {
+            bail!(EXPIRED_JWT_MSG);
+        }
+
         let jwt = self.jwt.as_ref().ok_or_else(|| anyhow!("Missing jwt"))?;
         let user_id = self
             .user_id
@@ -115,6 +201,9 @@ impl DuolingoApi {
             .send()
             .await?;
 
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            bail!(EXPIRED_JWT_MSG);
+        }
         if resp.status() != 200 {
             bail!(
                 "daily xp fetch returned status={}, {}",
@@ -161,6 +250,7 @@ impl DuolingoApi {
             .filter(|l| l.time > midnight)
             .collect();
         let xp_today = lessons_today.iter().map(|l| l.xp).sum();
+        tracing::Span::current().record("xp_today", xp_today);
 
         Ok(DailyProgress {
             xp_goal: daily.xp_goal,
@@ -0,0 +1,104 @@
+//! SQLite-backed history of observed lessons and daily XP roll-ups, so the
+//! "Recent Lessons" view survives a restart instead of only reflecting
+//! whatever the last `get_daily_progress()` call returned.
+
+use crate::Lesson;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+
+/// A single day's roll-up: total XP earned and whether the goal was met.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DayHistory {
+    pub date: String,
+    pub total_xp: i64,
+    pub goal_met: bool,
+}
+
+/// Opens (creating if needed) the SQLite store at `DATABASE_URL`, defaulting
+/// to a local file, and ensures the schema exists. This crate has no
+/// `migrations/` directory, so the `CREATE TABLE IF NOT EXISTS`s live here.
+pub async fn connect() -> Result<SqlitePool> {
+    let url =
+        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite://duo_history.db?mode=rwc".to_string());
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect(&url)
+        .await
+        .context("Failed to connect to history database")?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS lessons (
+            time INTEGER PRIMARY KEY,
+            xp INTEGER NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .context("Failed to create lessons table")?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS daily_rollup (
+            date TEXT PRIMARY KEY,
+            total_xp INTEGER NOT NULL,
+            goal_met INTEGER NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .context("Failed to create daily_rollup table")?;
+
+    Ok(pool)
+}
+
+/// Upserts each lesson keyed by `time`, so re-observing the same lesson
+/// across polls updates its row instead of duplicating it.
+pub async fn upsert_lessons(pool: &SqlitePool, lessons: &[Lesson]) -> Result<()> {
+    for lesson in lessons {
+        sqlx::query(
+            "INSERT INTO lessons (time, xp) VALUES (?, ?)
+             ON CONFLICT(time) DO UPDATE SET xp = excluded.xp",
+        )
+        .bind(lesson.time)
+        .bind(lesson.xp)
+        .execute(pool)
+        .await
+        .context("Failed to upsert lesson")?;
+    }
+    Ok(())
+}
+
+/// Upserts the roll-up row for `date`.
+pub async fn upsert_daily_rollup(pool: &SqlitePool, date: &str, total_xp: i64, goal_met: bool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO daily_rollup (date, total_xp, goal_met) VALUES (?, ?, ?)
+         ON CONFLICT(date) DO UPDATE SET total_xp = excluded.total_xp, goal_met = excluded.goal_met",
+    )
+    .bind(date)
+    .bind(total_xp)
+    .bind(goal_met)
+    .execute(pool)
+    .await
+    .context("Failed to upsert daily rollup")?;
+
+    Ok(())
+}
+
+/// Reads back every lesson observed in the last `days` days, oldest first,
+/// so callers can group them by day the same way the live API response is.
+pub async fn get_lessons_since(pool: &SqlitePool, days: u32) -> Result<Vec<Lesson>> {
+    let cutoff = Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+
+    let rows: Vec<(i64, i64)> =
+        sqlx::query_as("SELECT time, xp FROM lessons WHERE time > ? ORDER BY time ASC")
+            .bind(cutoff)
+            .fetch_all(pool)
+            .await
+            .context("Failed to read lesson history")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(time, xp)| Lesson { time, xp })
+        .collect())
+}
@@ -4,6 +4,12 @@ mod duo_driver;
 pub use duo_driver::*;
 #[cfg(feature = "ssr")]
 mod duolingo;
+#[cfg(feature = "ssr")]
+pub mod history;
+#[cfg(feature = "ssr")]
+pub mod config;
+#[cfg(feature = "ssr")]
+mod notify;
 
 pub mod ui;
 
@@ -36,6 +42,15 @@ pub struct SharedState {
     pub xp_goal: i64,
     pub lessons: Vec<Lesson>,
     pub last_error: Option<String>,
+    /// Unix timestamp the enforcement day rolls over at, per the loaded
+    /// `config::Config` (literal midnight unless a `grace_until` is set).
+    pub next_reset_at: i64,
+    /// Unix timestamp the current JWT's `exp` claim falls on, so the UI can
+    /// render a countdown before the user has to paste a fresh token. `None`
+    /// until a JWT has been decoded at least once.
+    pub token_expires_at: Option<i64>,
+    /// Whether `token_expires_at` is already in the past.
+    pub expired: bool,
 }
 
 impl Default for SharedState {
@@ -46,6 +61,9 @@ impl Default for SharedState {
             xp_goal: 0,
             lessons: vec![],
             last_error: None,
+            next_reset_at: 0,
+            token_expires_at: None,
+            expired: false,
         }
     }
 }
@@ -54,5 +72,6 @@ impl Default for SharedState {
 pub enum ActorCommand {
     UpdateJWT(String),
     ForcePoll,
+    ReloadConfig,
     Shutdown,
 }
@@ -0,0 +1,173 @@
+//! Webhook/ntfy-style and (optionally) SMTP notifications on block-state
+//! transitions.
+//!
+//! Fires only on edges — unblocked -> blocked, blocked -> unblocked (which is
+//! also how "goal reached" is observed, since that's what flips the block
+//! off) — never on every poll, so the user isn't pinged once a minute.
+
+use reqwest::Client;
+use tracing::warn;
+
+/// Priority levels ntfy.sh understands; anything outside 1-5 is clamped by
+/// the server, so we just pick the two we care about.
+const PRIORITY_DEFAULT: &str = "default";
+const PRIORITY_HIGH: &str = "high";
+
+/// `SMTP_HOST`/`SMTP_USER`/`SMTP_PASSWORD`/`ALERT_TO`, read once in
+/// `from_env`. Gated behind the `notifications` feature since it's the only
+/// thing pulling in `lettre`.
+#[cfg(feature = "notifications")]
+struct SmtpConfig {
+    host: String,
+    user: String,
+    password: String,
+    to: String,
+}
+
+pub struct Notifier {
+    client: Client,
+    webhook_url: Option<String>,
+    desktop: bool,
+    #[cfg(feature = "notifications")]
+    smtp: Option<SmtpConfig>,
+}
+
+impl Notifier {
+    /// Reads `NTFY_WEBHOOK_URL` (the POST target) and `DESKTOP_NOTIFICATIONS`
+    /// (any non-empty value enables a local `notify-send`) from the
+    /// environment, plus the `SMTP_*`/`ALERT_TO` vars when the
+    /// `notifications` feature is enabled. All are optional; with none set,
+    /// `notify_transition` is a no-op.
+    pub fn from_env() -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url: std::env::var("NTFY_WEBHOOK_URL")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            desktop: std::env::var("DESKTOP_NOTIFICATIONS")
+                .map(|s| !s.is_empty())
+                .unwrap_or(false),
+            #[cfg(feature = "notifications")]
+            smtp: match (
+                std::env::var("SMTP_HOST"),
+                std::env::var("SMTP_USER"),
+                std::env::var("SMTP_PASSWORD"),
+                std::env::var("ALERT_TO"),
+            ) {
+                (Ok(host), Ok(user), Ok(password), Ok(to)) => {
+                    Some(SmtpConfig { host, user, password, to })
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /// Notifies on a block-state transition: `was_blocked` -> `is_blocked`.
+    /// No-ops when the state didn't actually change.
+    ///
+    /// `detail` is appended to the SMTP alert body (e.g. XP counts, time til
+    /// reset) for callers that track that kind of state; ntfy/desktop keep
+    /// their short fixed body regardless. Returns the SMTP send error, if
+    /// any, so callers with a `last_error` field can surface it -- ntfy/
+    /// desktop failures are only logged, as before.
+    pub async fn notify_transition(
+        &self,
+        was_blocked: bool,
+        is_blocked: bool,
+        detail: &str,
+    ) -> Option<String> {
+        if was_blocked == is_blocked {
+            return None;
+        }
+
+        let (title, body, priority) = if is_blocked {
+            (
+                "Duolingo Enforcer: blocked",
+                "Today's XP goal hasn't been met yet. Sites are blocked until you do a lesson.",
+                PRIORITY_HIGH,
+            )
+        } else {
+            (
+                "Duolingo Enforcer: unblocked",
+                "Today's XP goal is met. Sites are unblocked.",
+                PRIORITY_DEFAULT,
+            )
+        };
+
+        self.send(title, body, priority).await;
+
+        #[cfg(feature = "notifications")]
+        {
+            let smtp = self.smtp.as_ref()?;
+            return Self::send_smtp(smtp, title, &format!("{body}\n\n{detail}")).err();
+        }
+
+        #[cfg(not(feature = "notifications"))]
+        {
+            let _ = detail;
+            None
+        }
+    }
+
+    async fn send(&self, title: &str, body: &str, priority: &str) {
+        if self.desktop {
+            if let Err(e) = std::process::Command::new("notify-send")
+                .arg(title)
+                .arg(body)
+                .spawn()
+            {
+                warn!("Failed to spawn desktop notification: {e:?}");
+            }
+        }
+
+        let Some(url) = &self.webhook_url else {
+            return;
+        };
+
+        let payload = serde_json::json!({
+            "title": title,
+            "message": body,
+            "priority": priority,
+        });
+        if let Err(e) = self.client.post(url).json(&payload).send().await {
+            warn!("Failed to send webhook notification: {e:?}");
+        }
+    }
+
+    /// Sends the SMTP alert via `lettre`'s blocking transport (the caller
+    /// already runs this off the async runtime via `block_on`). Send
+    /// failures -- including `SMTP_USER` not being a parseable RFC5322
+    /// mailbox, which some SMTP providers allow for auth -- are returned as
+    /// `Err` rather than unwrapped/panicking.
+    #[cfg(feature = "notifications")]
+    fn send_smtp(smtp: &SmtpConfig, subject: &str, body: &str) -> Result<(), String> {
+        use lettre::message::Message;
+        use lettre::transport::smtp::authentication::Credentials;
+        use lettre::{SmtpTransport, Transport};
+
+        let from = format!("Duolingo Enforcer <{}>", smtp.user)
+            .parse()
+            .map_err(|e| format!("Invalid SMTP_USER address: {e}"))?;
+        let to = smtp
+            .to
+            .parse()
+            .map_err(|e| format!("Invalid ALERT_TO address: {e}"))?;
+
+        let email = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(subject)
+            .body(body.to_string())
+            .map_err(|e| format!("Failed to build alert email: {e}"))?;
+
+        let mailer = SmtpTransport::relay(&smtp.host)
+            .map_err(|e| format!("Failed to build SMTP transport: {e}"))?
+            .credentials(Credentials::new(smtp.user.clone(), smtp.password.clone()))
+            .build();
+
+        mailer
+            .send(&email)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to send alert email: {e}"))
+    }
+}
@@ -4,7 +4,6 @@ use leptos::prelude::*;
 use leptos::task::spawn_local;
 use server_fn::error::NoCustomError;
 use std::collections::BTreeMap;
-use std::time::Duration;
 
 #[cfg(feature = "ssr")]
 fn try_send_command(cmd: ActorCommand) -> anyhow::Result<()> {
@@ -15,26 +14,177 @@ fn try_send_command(cmd: ActorCommand) -> anyhow::Result<()> {
     }
 }
 
+/// Name of the cookie `login` issues and the mutating server fns check for.
+#[cfg(feature = "ssr")]
+const SESSION_COOKIE: &str = "duo_admin_session";
+
+/// Holds the one currently-valid session token, if anyone has logged in.
+/// There's only ever one admin, so a single slot (rather than a table of
+/// sessions) matches the rest of this app's single-user assumptions.
+#[cfg(feature = "ssr")]
+static ADMIN_SESSION: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Pulls the request's `Cookie` header and checks it against `ADMIN_SESSION`,
+/// rejecting `update_jwt_server`/`force_poll_now` calls that don't carry a
+/// valid session -- otherwise the person being enforced could just clear
+/// their own JWT.
+#[cfg(feature = "ssr")]
+async fn check_session() -> Result<(), ServerFnError> {
+    use axum::http::{header::COOKIE, HeaderMap};
+
+    let headers: HeaderMap = leptos_axum::extract()
+        .await
+        .map_err(|e| ServerFnError::<NoCustomError>::ServerError(format!("Failed to read headers: {e}")))?;
+
+    let cookie_header = headers
+        .get(COOKIE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    let submitted = cookie_header.split(';').find_map(|pair| {
+        let (name, value) = pair.trim().split_once('=')?;
+        (name == SESSION_COOKIE).then(|| value.to_string())
+    });
+
+    let valid = ADMIN_SESSION.lock().unwrap();
+    match (submitted, valid.as_ref()) {
+        (Some(submitted), Some(current)) if &submitted == current => Ok(()),
+        _ => Err(ServerFnError::ServerError("Not logged in".to_string())),
+    }
+}
+
+/// Verifies `password` against the Argon2id PHC hash in `ADMIN_PASSWORD_HASH`
+/// and, on success, issues a session cookie that gates the mutating server
+/// fns below. Uses `argon2`'s constant-time `PasswordVerifier` so timing
+/// can't be used to brute-force the password, and never logs the raw value.
+#[server(Login, "/api")]
+#[tracing::instrument(skip(password))]
+pub async fn login(password: String) -> Result<(), ServerFnError> {
+    use argon2::password_hash::{PasswordHash, PasswordVerifier};
+    use argon2::Argon2;
+    use rand::distributions::Alphanumeric;
+    use rand::Rng;
+
+    let hash = std::env::var("ADMIN_PASSWORD_HASH").map_err(|_| {
+        ServerFnError::<NoCustomError>::ServerError("ADMIN_PASSWORD_HASH not configured".to_string())
+    })?;
+    let parsed_hash = PasswordHash::new(&hash)
+        .map_err(|e| ServerFnError::<NoCustomError>::ServerError(format!("Invalid ADMIN_PASSWORD_HASH: {e}")))?;
+
+    if Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        return Err(ServerFnError::ServerError("Incorrect password".to_string()));
+    }
+
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    *ADMIN_SESSION.lock().unwrap() = Some(token.clone());
+
+    let response = expect_context::<leptos_axum::ResponseOptions>();
+    let cookie = format!("{SESSION_COOKIE}={token}; HttpOnly; Path=/; SameSite=Strict");
+    response.insert_header(
+        axum::http::header::SET_COOKIE,
+        axum::http::HeaderValue::from_str(&cookie)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(format!("Bad cookie value: {e}")))?,
+    );
+
+    Ok(())
+}
+
 #[server(GetStatus, "/api")]
+#[tracing::instrument(fields(xp_today, blocked))]
 pub async fn get_status() -> Result<SharedState, ServerFnError> {
     let st = crate::duo_driver::SHARED_STATE.lock().unwrap().clone();
+    let span = tracing::Span::current();
+    span.record("xp_today", st.xp_today);
+    span.record("blocked", st.blocked);
     Ok(st)
 }
 
+/// `GET /api/sse` — streams a fresh `SharedState` frame every time `poll_duo`
+/// mutates it, so the client doesn't have to refetch `get_status` on a timer.
+/// Mount this alongside the Leptos routes in the server's Axum router.
+///
+/// This is the live-push channel for this track -- it supersedes the
+/// `/api/ws` broadcast channel originally built for the plain-axum app
+/// (`main.rs`, deleted when that track was retired); there is no separate
+/// WebSocket route here, this `Sse` stream is the one push mechanism.
+///
+/// `spawn_actor_thread` sets `STATUS_TX` from inside the actor thread, and
+/// `server.rs`'s `main` doesn't wait for that before calling `axum::serve`,
+/// so a request can land here before it's set -- that's a startup race, not
+/// a broken invariant, so return `503` rather than panicking the request.
+#[cfg(feature = "ssr")]
+#[tracing::instrument]
+pub async fn status_sse_handler() -> Result<
+    axum::response::Sse<impl futures::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>>,
+    axum::http::StatusCode,
+> {
+    use axum::response::sse::{Event, KeepAlive};
+    use futures::StreamExt;
+    use tokio_stream::wrappers::WatchStream;
+
+    let rx = crate::duo_driver::STATUS_TX
+        .get()
+        .ok_or(axum::http::StatusCode::SERVICE_UNAVAILABLE)?
+        .subscribe();
+
+    let stream = WatchStream::new(rx).map(|state| {
+        let json = serde_json::to_string(&state).unwrap_or_default();
+        Ok(Event::default().data(json))
+    });
+
+    Ok(axum::response::Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Reads back the last `days` days of observed lessons from the history
+/// store, so `OkUI` can show days beyond whatever `SharedState::lessons`
+/// happens to hold in memory since the last restart.
+#[server(GetHistory, "/api")]
+#[tracing::instrument]
+pub async fn get_history(days: u32) -> Result<Vec<Lesson>, ServerFnError> {
+    let pool = crate::duo_driver::HISTORY_POOL
+        .get()
+        .ok_or_else(|| ServerFnError::<NoCustomError>::ServerError("history db not ready".to_string()))?;
+    history::get_lessons_since(pool, days)
+        .await
+        .map_err(|e| ServerFnError::<NoCustomError>::ServerError(format!("Failed to read history: {e}")))
+}
+
 #[server(ForcePollNow, "/api")]
+#[tracing::instrument]
 pub async fn force_poll_now() -> Result<(), ServerFnError> {
+    check_session().await?;
     try_send_command(ActorCommand::ForcePoll).map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to send command: {e}"))
     })
 }
 
 #[server(UpdateJwt, "/api")]
+#[tracing::instrument(skip(new_jwt))]
 pub async fn update_jwt_server(new_jwt: String) -> Result<(), ServerFnError> {
+    check_session().await?;
     try_send_command(ActorCommand::UpdateJWT(new_jwt)).map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to send command: {e}"))
     })
 }
 
+/// Re-reads the TOML config (poll interval, per-weekday XP goals,
+/// grace_until) so schedule edits apply without restarting the server.
+#[server(ReloadConfig, "/api")]
+#[tracing::instrument]
+pub async fn reload_config() -> Result<(), ServerFnError> {
+    check_session().await?;
+    try_send_command(ActorCommand::ReloadConfig).map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to send command: {e}"))
+    })
+}
+
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
         <!DOCTYPE html>
@@ -55,18 +205,37 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
     }
 }
 
-/// The main Leptos app component: sets up the resource and calls `RenderStatus`.
+/// The main Leptos app component: hydrates from `get_status` once, then lets
+/// the `/api/sse` stream keep it fresh instead of polling on a timer.
 #[component]
 pub fn App() -> impl IntoView {
     let status_res = Resource::new(|| (), |_| async move { get_status().await });
+    let history_res = Resource::new(|| (), |_| async move { get_history(7).await });
 
-    // We'll poll it every 5 seconds automatically
-    set_interval(
-        move || {
-            status_res.refetch();
-        },
-        Duration::from_secs(5),
-    );
+    // Pushed to whenever an `/api/sse` frame arrives; `None` until hydrated.
+    let (live_status, set_live_status) = signal(None::<Result<SharedState, ServerFnError>>);
+
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::{prelude::Closure, JsCast};
+        use web_sys::{EventSource, MessageEvent};
+
+        Effect::new(move |_| {
+            let Ok(source) = EventSource::new("/api/sse") else {
+                return;
+            };
+
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+                if let Some(text) = ev.data().as_string() {
+                    if let Ok(state) = serde_json::from_str::<SharedState>(&text) {
+                        set_live_status.set(Some(Ok(state)));
+                    }
+                }
+            });
+            source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            on_message.forget();
+        });
+    }
 
     // For typed JWT input
     let (jwt_input, set_jwt_input) = signal(String::new());
@@ -75,13 +244,17 @@ pub fn App() -> impl IntoView {
         <h1>"Duolingo Enforcer"</h1>
         <Suspense fallback=move || view! {  <p>"Loading..."</p> }>
             {move || {
-                status_res.get().map(|maybe_status| {
+                // Prefer the live SSE-pushed state once it has arrived;
+                // `status_res` only ever supplies the initial hydration snapshot.
+                let maybe_status = live_status.get().or_else(|| status_res.get());
+                maybe_status.map(|maybe_status| {
                     // We'll pass the result (Ok/Err) into a subcomponent
                     // along with the signals we need for the "Ok" UI.
                     view! {
                         <RenderStatus
                             maybe_status=maybe_status
                             status_res=status_res
+                            history_res=history_res
                             jwt_input=jwt_input
                             set_jwt_input=set_jwt_input
                         />
@@ -100,6 +273,8 @@ fn RenderStatus(
     maybe_status: Result<SharedState, ServerFnError>,
     /// We'll need to call `status_res.refetch()` after certain actions
     status_res: Resource<Result<SharedState, ServerFnError>>,
+    /// The last week of persisted lesson history, independent of `SharedState`.
+    history_res: Resource<Result<Vec<Lesson>, ServerFnError>>,
     /// The JWT input signal
     jwt_input: ReadSignal<String>,
     set_jwt_input: WriteSignal<String>,
@@ -109,6 +284,7 @@ fn RenderStatus(
             <OkUI
                 status=status
                 status_res=status_res
+                history_res=history_res
                 jwt_input=jwt_input
                 set_jwt_input=set_jwt_input
             />
@@ -126,22 +302,15 @@ fn RenderStatus(
 fn OkUI(
     status: SharedState,
     status_res: Resource<Result<SharedState, ServerFnError>>,
+    history_res: Resource<Result<Vec<Lesson>, ServerFnError>>,
     jwt_input: ReadSignal<String>,
     set_jwt_input: WriteSignal<String>,
 ) -> impl IntoView {
-    // We define a helper to display time to midnight
-    fn time_until_midnight() -> String {
-        let now = Local::now();
-        let midnight = now
-            .date_naive()
-            .succ_opt() // next day
-            .unwrap_or(now.date_naive())
-            .and_hms_opt(0, 0, 0)
-            .unwrap_or(now.naive_local());
-        let diff = midnight - now.naive_local();
-        let hours = diff.num_hours();
-        let minutes = (diff.num_minutes() % 60).abs();
-        format!("{hours}h {minutes}m")
+    // Against `next_reset_at` (the configured schedule's cutoff) rather than
+    // literal midnight, so a `grace_until` in the config shows up here too.
+    fn time_until_reset(next_reset_at: i64) -> String {
+        let diff = next_reset_at - chrono::Utc::now().timestamp();
+        format!("{}h {}m", diff / 3600, (diff / 60) % 60)
     }
 
     let color = if status.blocked { "red" } else { "green" };
@@ -152,15 +321,29 @@ fn OkUI(
     };
 
     let parted = if !status.blocked {
-        format!("(Time til midnight: {})", time_until_midnight())
+        format!("(Time til reset: {})", time_until_reset(status.next_reset_at))
     } else {
         "".to_string()
     };
 
+    // Warn before the JWT itself expires, independent of the block/unblock
+    // state above -- a token can expire while unblocked and still need a
+    // fresh paste before the next poll.
+    let jwt_html = match (status.expired, status.token_expires_at) {
+        (true, _) => "JWT expired -- paste a fresh token".to_string(),
+        (false, Some(exp)) => format!("JWT expires in {}", time_until_reset(exp)),
+        (false, None) => "".to_string(),
+    };
+
     let err_html = status.last_error.clone().unwrap_or_default();
 
+    // Prefer the persisted history (spans multiple days); fall back to
+    // whatever `SharedState` holds in memory if the history db isn't ready.
+    let history_lessons = history_res.get().and_then(Result::ok);
+    let lessons = history_lessons.as_ref().unwrap_or(&status.lessons);
+
     let mut day_map: BTreeMap<String, Vec<Lesson>> = BTreeMap::new();
-    for lesson in &status.lessons {
+    for lesson in lessons {
         let day_str = Local
             .timestamp_opt(lesson.time, 0)
             .single()
@@ -177,6 +360,9 @@ fn OkUI(
                 {main_text} " " {parted}
             </p>
             <p style="color:red;">{err_html}</p>
+            <p style=if status.expired { "color:red;" } else { "color:gray;" }>{jwt_html}</p>
+
+            <LoginForm/>
 
             // "Scan Now" => calls force_poll_now()
             <button on:click=move |_| {
@@ -200,6 +386,12 @@ fn OkUI(
                         status_res.refetch();
                     });
                 }>"Update JWT"</button>
+                <button on:click=move |_| {
+                    spawn_local(async move {
+                        let _ = reload_config().await;
+                        status_res.refetch();
+                    });
+                }>"Reload Config"</button>
             </div>
 
             <hr/>
@@ -230,6 +422,38 @@ fn OkUI(
     }
 }
 
+/// Small admin login form. Posts to `login`, which sets the session cookie
+/// that `update_jwt_server`/`force_poll_now` require; shows whatever message
+/// the server returned (success or failure) rather than tracking auth state
+/// client-side.
+#[component]
+fn LoginForm() -> impl IntoView {
+    let (password, set_password) = signal(String::new());
+    let (message, set_message) = signal(String::new());
+
+    view! {
+        <div style="margin-top: 1em;">
+            <input
+                type="password"
+                placeholder="Admin password..."
+                prop:value=password
+                on:input=move |ev| set_password.set(event_target_value(&ev))
+                style="width: 300px;"
+            />
+            <button on:click=move |_| {
+                let submitted = password.get_untracked();
+                spawn_local(async move {
+                    set_message.set(match login(submitted).await {
+                        Ok(()) => "Logged in".to_string(),
+                        Err(e) => format!("Login failed: {e}"),
+                    });
+                });
+            }>"Log In"</button>
+            <span style="margin-left: 1em;">{message}</span>
+        </div>
+    }
+}
+
 /// Subcomponent for the "error" state.
 #[component]
 fn ErrUI(err: ServerFnError) -> impl IntoView {